@@ -1,29 +1,185 @@
 pub use unrestrictive_url::ParseError;
-use unrestrictive_url::{UnrestrictiveUrl, Url};
+use unrestrictive_url::{Host, UnrestrictiveUrlBuf, Url};
 
-pub fn humanize_url(url: &str) -> Result<String, ParseError> {
-    let url = Url::parse(url)?;
-    let mut url = UnrestrictiveUrl::from(&url);
-
-    // Remove protocol.
-    url.scheme = None;
-    // Remove authentication.
-    url.username = None;
-    url.password = None;
-
-    // Remove trailing slashes.
-    let url = url.to_string();
-    let mut chars = url.chars();
-    if chars.next_back() == Some('/') {
-        Ok(chars.collect())
+mod punycode;
+
+/// Configurable builder for turning a URL into a human-friendly string.
+///
+/// `humanize_url` hard-codes exactly one set of choices (strip the protocol, strip
+/// authentication, strip one trailing slash). `Humanizer` always makes those choices too, and adds
+/// options on top of them, so callers can additionally drop the query or fragment, strip a leading `www.`, reduce the URL
+/// down to just its host, collapse an explicit default port, or decode Punycode domain labels to
+/// Unicode.
+///
+/// # Example
+///
+/// ```rust
+/// use humanize_url::Humanizer;
+///
+/// let url = Humanizer::new()
+///     .drop_query(true)
+///     .strip_www(true)
+///     .humanize("https://www.github.com?tab=repositories")
+///     .unwrap();
+///
+/// assert_eq!("github.com", url);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Humanizer {
+    drop_query: bool,
+    drop_fragment: bool,
+    strip_www: bool,
+    host_only: bool,
+    collapse_default_port: bool,
+    unicode_host: bool,
+}
+
+impl Humanizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drops the query string. Off by default.
+    pub fn drop_query(mut self, drop_query: bool) -> Self {
+        self.drop_query = drop_query;
+        self
+    }
+
+    /// Drops the fragment. Off by default.
+    pub fn drop_fragment(mut self, drop_fragment: bool) -> Self {
+        self.drop_fragment = drop_fragment;
+        self
+    }
+
+    /// Strips a leading `www.` label from a domain host. Off by default.
+    pub fn strip_www(mut self, strip_www: bool) -> Self {
+        self.strip_www = strip_www;
+        self
+    }
+
+    /// Drops the path, query, and fragment, leaving just the host. Off by default.
+    pub fn host_only(mut self, host_only: bool) -> Self {
+        self.host_only = host_only;
+        self
+    }
+
+    /// Collapses the port if it is the scheme's well-known default (e.g. `:443` for `https`).
+    /// Off by default.
+    pub fn collapse_default_port(mut self, collapse_default_port: bool) -> Self {
+        self.collapse_default_port = collapse_default_port;
+        self
+    }
+
+    /// Decodes Punycode (`xn--`) domain labels to Unicode (e.g. `xn--bcher-kva.example` becomes
+    /// `bücher.example`). Off by default.
+    pub fn unicode_host(mut self, unicode_host: bool) -> Self {
+        self.unicode_host = unicode_host;
+        self
+    }
+
+    pub fn humanize(&self, url: &str) -> Result<String, ParseError> {
+        let url = Url::parse(url)?;
+        let mut url = UnrestrictiveUrlBuf::from(&url);
+        let original_scheme = url.scheme.clone();
+
+        // Remove protocol.
+        url.scheme = None;
+        // Remove authentication.
+        url.username = None;
+        url.password = None;
+
+        if self.drop_query {
+            url.query = None;
+        }
+        if self.drop_fragment {
+            url.fragment = None;
+        }
+        if self.host_only {
+            url.path = None;
+            url.query = None;
+            url.fragment = None;
+        }
+
+        if self.strip_www {
+            if let Some(Host::Domain(domain)) = &url.host {
+                if let Some(stripped) = domain.strip_prefix("www.") {
+                    url.host = Some(Host::Domain(stripped.to_string()));
+                }
+            }
+        }
+
+        if self.unicode_host {
+            if let Some(Host::Domain(domain)) = &url.host {
+                url.host = Some(Host::Domain(punycode::to_unicode_host(domain)));
+            }
+        }
+
+        if self.collapse_default_port {
+            url.port = collapse_default_port(original_scheme.as_deref(), url.port);
+        }
+
+        // Remove one trailing slash.
+        let url = url.to_string();
+        let mut chars = url.chars();
+        if chars.next_back() == Some('/') {
+            Ok(chars.collect())
+        } else {
+            Ok(url)
+        }
+    }
+}
+
+fn default_port(scheme: &str) -> Option<u16> {
+    match scheme {
+        "http" | "ws" => Some(80),
+        "https" | "wss" => Some(443),
+        "ftp" => Some(21),
+        _ => None,
+    }
+}
+
+/// Drops `port` if it's the well-known default for `scheme`, keeping it otherwise.
+///
+/// Takes the scheme explicitly (rather than reading it off the URL being built) because by the
+/// time this runs the scheme has already been stripped from the humanized output.
+fn collapse_default_port(scheme: Option<&str>, port: Option<u16>) -> Option<u16> {
+    if port == default_port(scheme.unwrap_or_default()) {
+        None
     } else {
-        Ok(url)
+        port
     }
 }
 
+pub fn humanize_url(url: &str) -> Result<String, ParseError> {
+    Humanizer::new().humanize(url)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::humanize_url;
+    use super::{collapse_default_port, humanize_url, Humanizer};
+
+    // `url::Url::parse` itself already elides an explicit port that matches one of its five
+    // recognized "special" schemes' defaults (the same five `default_port` knows about), so a
+    // `Humanizer::humanize` test can never observe `collapse_default_port` actually dropping an
+    // explicit port — by the time it runs, `url.port` is already `None`. Exercise the helper
+    // directly instead.
+    #[test]
+    fn collapse_default_port_drops_an_explicit_default_port() {
+        assert_eq!(None, collapse_default_port(Some("https"), Some(443)));
+    }
+
+    #[test]
+    fn collapse_default_port_keeps_a_non_default_port() {
+        assert_eq!(
+            Some(8443),
+            collapse_default_port(Some("https"), Some(8443))
+        );
+    }
+
+    #[test]
+    fn collapse_default_port_keeps_no_port_as_is() {
+        assert_eq!(None, collapse_default_port(Some("https"), None));
+    }
 
     #[test]
     fn removes_scheme() {
@@ -42,4 +198,79 @@ mod tests {
         let url = humanize_url("https://user:pw@github.com/SirWindfield").unwrap();
         assert_eq!("github.com/SirWindfield", url);
     }
+
+    #[test]
+    fn keeps_query_and_fragment_by_default() {
+        let url = humanize_url("https://github.com?q=search#readme").unwrap();
+        assert_eq!("github.com/?q=search#readme", url);
+    }
+
+    #[test]
+    fn drops_query() {
+        let url = Humanizer::new()
+            .drop_query(true)
+            .humanize("https://github.com?q=search")
+            .unwrap();
+        assert_eq!("github.com", url);
+    }
+
+    #[test]
+    fn drops_fragment() {
+        let url = Humanizer::new()
+            .drop_fragment(true)
+            .humanize("https://github.com#readme")
+            .unwrap();
+        assert_eq!("github.com", url);
+    }
+
+    #[test]
+    fn strips_www() {
+        let url = Humanizer::new()
+            .strip_www(true)
+            .humanize("https://www.github.com?q=search")
+            .unwrap();
+        assert_eq!("github.com/?q=search", url);
+    }
+
+    #[test]
+    fn host_only_drops_path_query_and_fragment() {
+        let url = Humanizer::new()
+            .host_only(true)
+            .humanize("https://github.com/SirWindfield?q=search#readme")
+            .unwrap();
+        assert_eq!("github.com", url);
+    }
+
+    // There's no end-to-end `Humanizer::humanize` test for the "drops" side of
+    // `collapse_default_port`: `url::Url::parse` already elides an explicit port matching
+    // one of its five special schemes' defaults (the same five `default_port` recognizes)
+    // before `humanize` ever sees it, so such a test would pass identically whether or not
+    // the option did anything. `collapse_default_port_drops_an_explicit_default_port` above
+    // covers the logic directly instead.
+    #[test]
+    fn keeps_non_default_port() {
+        let url = Humanizer::new()
+            .collapse_default_port(true)
+            .humanize("https://github.com:8443?q=search")
+            .unwrap();
+        assert_eq!("github.com:8443/?q=search", url);
+    }
+
+    #[test]
+    fn decodes_unicode_host() {
+        let url = Humanizer::new()
+            .unicode_host(true)
+            .humanize("https://xn--bcher-kva.example")
+            .unwrap();
+        assert_eq!("bücher.example", url);
+    }
+
+    #[test]
+    fn leaves_non_punycode_host_untouched() {
+        let url = Humanizer::new()
+            .unicode_host(true)
+            .humanize("https://github.com")
+            .unwrap();
+        assert_eq!("github.com", url);
+    }
 }
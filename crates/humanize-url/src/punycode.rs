@@ -0,0 +1,137 @@
+//! A minimal Punycode ([RFC 3492](https://www.rfc-editor.org/rfc/rfc3492)) decoder, used to
+//! render ACE-encoded IDNA domain labels (e.g. `xn--bcher-kva`) back into Unicode for
+//! human-facing display.
+
+const ACE_PREFIX: &str = "xn--";
+
+const BASE: u32 = 36;
+const TMIN: u32 = 1;
+const TMAX: u32 = 26;
+const SKEW: u32 = 38;
+const DAMP: u32 = 700;
+const INITIAL_BIAS: u32 = 72;
+const INITIAL_N: u32 = 128;
+
+fn adapt(mut delta: u32, num_points: u32, first_time: bool) -> u32 {
+    delta /= if first_time { DAMP } else { 2 };
+    delta += delta / num_points;
+
+    let mut k = 0;
+    while delta > ((BASE - TMIN) * TMAX) / 2 {
+        delta /= BASE - TMIN;
+        k += BASE;
+    }
+    k + ((BASE - TMIN + 1) * delta) / (delta + SKEW)
+}
+
+fn decode_digit(byte: u8) -> Option<u32> {
+    match byte {
+        b'a'..=b'z' => Some((byte - b'a') as u32),
+        b'A'..=b'Z' => Some((byte - b'A') as u32),
+        b'0'..=b'9' => Some((byte - b'0') as u32 + 26),
+        _ => None,
+    }
+}
+
+/// Decodes the Bootstring-encoded part of a Punycode label (with the `xn--` ACE prefix already
+/// stripped) into Unicode, returning `None` on any invalid sequence.
+fn decode(input: &str) -> Option<String> {
+    if !input.is_ascii() {
+        return None;
+    }
+    let input = input.as_bytes();
+
+    let (basic, extended) = match input.iter().rposition(|&b| b == b'-') {
+        Some(pos) => (&input[..pos], &input[pos + 1..]),
+        None => (&input[..0], input),
+    };
+    let mut output: Vec<u32> = basic.iter().map(|&b| b as u32).collect();
+
+    let mut n = INITIAL_N;
+    let mut i: u32 = 0;
+    let mut bias = INITIAL_BIAS;
+    let mut pos = 0;
+
+    while pos < extended.len() {
+        let old_i = i;
+        let mut w: u32 = 1;
+        let mut k = BASE;
+        loop {
+            let digit = decode_digit(*extended.get(pos)?)?;
+            pos += 1;
+
+            i = i.checked_add(digit.checked_mul(w)?)?;
+
+            let t = if k <= bias {
+                TMIN
+            } else if k >= bias + TMAX {
+                TMAX
+            } else {
+                k - bias
+            };
+            if digit < t {
+                break;
+            }
+
+            w = w.checked_mul(BASE - t)?;
+            k += BASE;
+        }
+
+        let num_points = output.len() as u32 + 1;
+        bias = adapt(i - old_i, num_points, old_i == 0);
+        n = n.checked_add(i / num_points)?;
+        i %= num_points;
+
+        output.insert(i as usize, n);
+        i += 1;
+    }
+
+    output.into_iter().map(char::from_u32).collect()
+}
+
+/// Decodes a single `.`-separated domain label to Unicode if it carries the `xn--` ACE prefix,
+/// falling back to the original label unchanged if it doesn't, or if decoding fails.
+fn to_unicode_label(label: &str) -> String {
+    match label.strip_prefix(ACE_PREFIX) {
+        Some(encoded) => decode(encoded).unwrap_or_else(|| label.to_string()),
+        None => label.to_string(),
+    }
+}
+
+/// Decodes every Punycode-encoded label of a domain host to Unicode, leaving other labels
+/// untouched.
+pub(crate) fn to_unicode_host(domain: &str) -> String {
+    domain
+        .split('.')
+        .map(to_unicode_label)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_unicode_host;
+
+    #[test]
+    fn decodes_single_label() {
+        assert_eq!("bücher.example", to_unicode_host("xn--bcher-kva.example"));
+    }
+
+    #[test]
+    fn decodes_multiple_labels() {
+        assert_eq!(
+            "bücher.münchen.de",
+            to_unicode_host("xn--bcher-kva.xn--mnchen-3ya.de")
+        );
+    }
+
+    #[test]
+    fn leaves_non_ace_labels_untouched() {
+        assert_eq!("github.com", to_unicode_host("github.com"));
+    }
+
+    #[test]
+    fn falls_back_to_original_on_invalid_sequence() {
+        assert_eq!("xn---!!!", to_unicode_host("xn---!!!"));
+    }
+}
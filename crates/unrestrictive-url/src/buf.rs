@@ -0,0 +1,440 @@
+use std::{fmt, str::Split};
+
+use url::{form_urlencoded, Host};
+
+use crate::components::{self, Components};
+use crate::position::Serialized;
+
+/// The decoded `key=value` pairs backing `query`, paired with the `query` value they were
+/// built from.
+type QueryPairsIndex = (Option<String>, Vec<(String, String)>);
+
+/// An owned, mutable sibling of [`crate::UnrestrictiveUrl`].
+///
+/// Every field on [`crate::UnrestrictiveUrl`] borrows from a source
+/// [`url::Url`], which is enough to null components out but not to replace
+/// them with new values. `UnrestrictiveUrlBuf` owns its data instead, so it
+/// can be built up from scratch with [`Default`] or edited piece by piece,
+/// and still serializes with the exact same WHATWG-faithful logic via
+/// [`fmt::Display`].
+///
+/// # Example
+///
+/// ```rust
+/// use unrestrictive_url::{Url, UnrestrictiveUrlBuf};
+///
+/// let url = Url::parse("https://github.com").unwrap();
+/// let mut url = UnrestrictiveUrlBuf::from(&url);
+/// url.scheme = Some("jojo".to_string());
+///
+/// assert_eq!("jojo://github.com/", url.to_string());
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct UnrestrictiveUrlBuf {
+    pub fragment: Option<String>,
+    pub host: Option<Host<String>>,
+    pub password: Option<String>,
+    pub path: Option<String>,
+    pub port: Option<u16>,
+    pub query: Option<String>,
+    pub scheme: Option<String>,
+    pub username: Option<String>,
+    cannot_be_a_base: bool,
+    // Built lazily the first time a `*_query_pair` method is called so that constructing or
+    // reading a URL whose query is never edited doesn't pay for parsing it, and rebuilt
+    // whenever a direct write to the public `query` field has invalidated it.
+    query_pairs_index: Option<QueryPairsIndex>,
+}
+
+impl PartialEq for UnrestrictiveUrlBuf {
+    fn eq(&self, other: &Self) -> bool {
+        self.fragment == other.fragment
+            && self.host == other.host
+            && self.password == other.password
+            && self.path == other.path
+            && self.port == other.port
+            && self.query == other.query
+            && self.scheme == other.scheme
+            && self.username == other.username
+            && self.cannot_be_a_base == other.cannot_be_a_base
+    }
+}
+
+impl Eq for UnrestrictiveUrlBuf {}
+
+impl UnrestrictiveUrlBuf {
+    pub fn path_segments(&self) -> Option<Split<'_, char>> {
+        self.path
+            .as_deref()
+            .and_then(|v| v.strip_prefix('/'))
+            .map(|v| v.split('/'))
+    }
+
+    pub fn set_scheme(mut self, scheme: impl Into<String>) -> Self {
+        self.scheme = Some(scheme.into());
+        self
+    }
+
+    pub fn set_username(mut self, username: impl Into<String>) -> Self {
+        self.username = Some(username.into());
+        self
+    }
+
+    pub fn set_password(mut self, password: impl Into<String>) -> Self {
+        self.password = Some(password.into());
+        self
+    }
+
+    pub fn set_host(mut self, host: Host<String>) -> Self {
+        self.host = Some(host);
+        self
+    }
+
+    pub fn set_port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    pub fn set_path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    pub fn set_query(mut self, query: impl Into<String>) -> Self {
+        self.query = Some(query.into());
+        self
+    }
+
+    pub fn set_fragment(mut self, fragment: impl Into<String>) -> Self {
+        self.fragment = Some(fragment.into());
+        self
+    }
+
+    /// Iterates over the query as percent-decoded `key=value` pairs, in their original order.
+    ///
+    /// This reads straight from `query` and never touches the lazily-built edit index, so
+    /// calling it repeatedly on an untouched URL is free.
+    pub fn query_pairs(&self) -> form_urlencoded::Parse<'_> {
+        form_urlencoded::parse(self.query.as_deref().unwrap_or("").as_bytes())
+    }
+
+    /// Sets `key` to `value`, replacing its first existing occurrence (and dropping any further
+    /// ones) or appending a new pair if `key` isn't present yet.
+    pub fn set_query_pair(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        let key = key.into();
+        let value = value.into();
+
+        let pairs = self.query_pairs_index();
+        let mut replaced = false;
+        pairs.retain_mut(|(k, v)| {
+            if *k != key {
+                return true;
+            }
+            if replaced {
+                return false;
+            }
+            *v = value.clone();
+            replaced = true;
+            true
+        });
+        if !replaced {
+            pairs.push((key, value));
+        }
+
+        self.sync_query_from_index();
+        self
+    }
+
+    /// Removes every pair whose key is `key`.
+    pub fn remove_query_pair(mut self, key: &str) -> Self {
+        self.query_pairs_index().retain(|(k, _)| k != key);
+        self.sync_query_from_index();
+        self
+    }
+
+    /// Appends `pairs` to the query as-is, without touching or deduplicating against any
+    /// existing pairs (so a repeated key ends up with multiple values, preserved in order).
+    pub fn append_query_pairs<K, V>(mut self, pairs: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.query_pairs_index()
+            .extend(pairs.into_iter().map(|(k, v)| (k.into(), v.into())));
+        self.sync_query_from_index();
+        self
+    }
+
+    fn query_pairs_index(&mut self) -> &mut Vec<(String, String)> {
+        // A direct write to `query` since the index was last built invalidates it.
+        let stale = self
+            .query_pairs_index
+            .as_ref()
+            .is_some_and(|(built_from, _)| *built_from != self.query);
+        if stale {
+            self.query_pairs_index = None;
+        }
+
+        if self.query_pairs_index.is_none() {
+            let pairs = self
+                .query
+                .as_deref()
+                .map(|query| {
+                    form_urlencoded::parse(query.as_bytes())
+                        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+                        .collect()
+                })
+                .unwrap_or_default();
+            self.query_pairs_index = Some((self.query.clone(), pairs));
+        }
+
+        &mut self.query_pairs_index.as_mut().unwrap().1
+    }
+
+    fn sync_query_from_index(&mut self) {
+        let pairs = &self.query_pairs_index.as_ref().unwrap().1;
+        self.query = if pairs.is_empty() {
+            None
+        } else {
+            let mut serializer = form_urlencoded::Serializer::new(String::new());
+            serializer.extend_pairs(pairs);
+            Some(serializer.finish())
+        };
+        // Keep the index's snapshot of `query` in sync with what we just wrote, so the
+        // next `query_pairs_index()` call doesn't see itself as stale.
+        self.query_pairs_index.as_mut().unwrap().0 = self.query.clone();
+    }
+
+    /// Serializes the URL and precomputes the byte offset of every [`crate::Position`], so the
+    /// result can be sliced with `Position`-based indexing (e.g. `&serialized[Position::BeforePath..]`).
+    pub fn serialize(&self) -> Serialized {
+        Serialized::new(self)
+    }
+}
+
+impl Components for UnrestrictiveUrlBuf {
+    fn scheme(&self) -> Option<&str> {
+        self.scheme.as_deref()
+    }
+
+    fn username(&self) -> Option<&str> {
+        self.username.as_deref()
+    }
+
+    fn password(&self) -> Option<&str> {
+        self.password.as_deref()
+    }
+
+    fn host(&self) -> Option<Host<&str>> {
+        self.host.as_ref().map(components::host_as_str)
+    }
+
+    fn port(&self) -> Option<u16> {
+        self.port
+    }
+
+    fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    fn query(&self) -> Option<&str> {
+        self.query.as_deref()
+    }
+
+    fn fragment(&self) -> Option<&str> {
+        self.fragment.as_deref()
+    }
+
+    fn cannot_be_a_base(&self) -> bool {
+        self.cannot_be_a_base
+    }
+}
+
+impl fmt::Display for UnrestrictiveUrlBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        components::fmt(self, f)
+    }
+}
+
+impl From<&url::Url> for UnrestrictiveUrlBuf {
+    fn from(url: &url::Url) -> Self {
+        let username = if url.username().is_empty() {
+            None
+        } else {
+            Some(url.username().to_string())
+        };
+
+        Self {
+            fragment: url.fragment().map(str::to_string),
+            host: url.host().map(|host| match host {
+                Host::Domain(v) => Host::Domain(v.to_string()),
+                Host::Ipv4(v) => Host::Ipv4(v),
+                Host::Ipv6(v) => Host::Ipv6(v),
+            }),
+            password: url.password().map(str::to_string),
+            path: Some(url.path().to_string()),
+            port: url.port(),
+            query: url.query().map(str::to_string),
+            scheme: Some(url.scheme().to_string()),
+            username,
+            cannot_be_a_base: url.cannot_be_a_base(),
+            query_pairs_index: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UnrestrictiveUrlBuf;
+    use crate::Url;
+
+    #[test]
+    fn test_arbitrary_scheme() {
+        let url = "https://github.com";
+        let url = Url::parse(url).unwrap();
+        let mut url = UnrestrictiveUrlBuf::from(&url);
+        url.scheme = Some("github".to_string());
+
+        assert_eq!("github://github.com/", url.to_string());
+    }
+
+    #[test]
+    fn test_remove_scheme() {
+        let url = "https://github.com";
+        let url = Url::parse(url).unwrap();
+        let mut url = UnrestrictiveUrlBuf::from(&url);
+        url.scheme = None;
+
+        assert_eq!("github.com/", url.to_string());
+    }
+
+    #[test]
+    fn test_remove_fragment() {
+        let url = "https://github.com#fragment";
+        let url = Url::parse(url).unwrap();
+        let mut url = UnrestrictiveUrlBuf::from(&url);
+        url.fragment = None;
+
+        assert_eq!("https://github.com/", url.to_string());
+    }
+
+    #[test]
+    fn test_remove_query() {
+        let url = "https://github.com?q=search&otherstuff=5";
+        let url = Url::parse(url).unwrap();
+        let mut url = UnrestrictiveUrlBuf::from(&url);
+        url.query = None;
+
+        assert_eq!("https://github.com/", url.to_string());
+    }
+
+    #[test]
+    fn test_remove_password() {
+        let url = "https://user:pw@github.com";
+        let url = Url::parse(url).unwrap();
+        let mut url = UnrestrictiveUrlBuf::from(&url);
+        url.password = None;
+
+        assert_eq!("https://user@github.com/", url.to_string());
+    }
+
+    #[test]
+    fn test_remove_username() {
+        let url = "https://user:pw@github.com";
+        let url = Url::parse(url).unwrap();
+        let mut url = UnrestrictiveUrlBuf::from(&url);
+        url.username = None;
+
+        assert_eq!("https://github.com/", url.to_string());
+    }
+
+    #[test]
+    fn test_build_from_scratch() {
+        let url = UnrestrictiveUrlBuf::default()
+            .set_scheme("https")
+            .set_host(url::Host::Domain("github.com".to_string()))
+            .set_query("q=search");
+
+        assert_eq!("https://github.com?q=search", url.to_string());
+    }
+
+    #[test]
+    fn test_query_pairs_iterates_decoded_in_order() {
+        let url = "https://github.com?q=hello%20world&tag=a&tag=b";
+        let url = Url::parse(url).unwrap();
+        let url = UnrestrictiveUrlBuf::from(&url);
+
+        let pairs: Vec<_> = url
+            .query_pairs()
+            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("q".to_string(), "hello world".to_string()),
+                ("tag".to_string(), "a".to_string()),
+                ("tag".to_string(), "b".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_set_query_pair_replaces_existing_value() {
+        let url = "https://github.com?q=search&otherstuff=5";
+        let url = Url::parse(url).unwrap();
+        let url = UnrestrictiveUrlBuf::from(&url).set_query_pair("q", "new search");
+
+        assert_eq!(
+            "https://github.com/?q=new+search&otherstuff=5",
+            url.to_string()
+        );
+    }
+
+    #[test]
+    fn test_set_query_pair_appends_new_key() {
+        let url = UnrestrictiveUrlBuf::default()
+            .set_scheme("https")
+            .set_host(url::Host::Domain("github.com".to_string()))
+            .set_query_pair("q", "search");
+
+        assert_eq!("https://github.com?q=search", url.to_string());
+    }
+
+    #[test]
+    fn test_remove_query_pair() {
+        let url = "https://github.com?q=search&otherstuff=5";
+        let url = Url::parse(url).unwrap();
+        let url = UnrestrictiveUrlBuf::from(&url).remove_query_pair("otherstuff");
+
+        assert_eq!("https://github.com/?q=search", url.to_string());
+    }
+
+    #[test]
+    fn test_remove_last_query_pair_clears_query() {
+        let url = "https://github.com?q=search";
+        let url = Url::parse(url).unwrap();
+        let url = UnrestrictiveUrlBuf::from(&url).remove_query_pair("q");
+
+        assert_eq!("https://github.com/", url.to_string());
+    }
+
+    #[test]
+    fn test_direct_query_write_invalidates_query_pairs_index() {
+        let url = UnrestrictiveUrlBuf::default()
+            .set_query_pair("x", "y")
+            .set_query("z=9");
+        let url = url.set_query_pair("w", "9");
+
+        assert_eq!("z=9&w=9", url.query.unwrap());
+    }
+
+    #[test]
+    fn test_append_query_pairs_preserves_repeated_keys() {
+        let url = "https://github.com?tag=a";
+        let url = Url::parse(url).unwrap();
+        let url =
+            UnrestrictiveUrlBuf::from(&url).append_query_pairs([("tag", "b"), ("tag", "c")]);
+
+        assert_eq!("https://github.com/?tag=a&tag=b&tag=c", url.to_string());
+    }
+}
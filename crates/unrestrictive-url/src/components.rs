@@ -0,0 +1,175 @@
+use std::fmt::{self, Write as _};
+
+use url::Host;
+
+/// Accessors shared by the borrowed [`crate::UnrestrictiveUrl`] and the owned
+/// [`crate::UnrestrictiveUrlBuf`], so both can be serialized with exactly the
+/// same WHATWG-faithful logic instead of duplicating it per type.
+pub(crate) trait Components {
+    fn scheme(&self) -> Option<&str>;
+    fn username(&self) -> Option<&str>;
+    fn password(&self) -> Option<&str>;
+    fn host(&self) -> Option<Host<&str>>;
+    fn port(&self) -> Option<u16>;
+    fn path(&self) -> Option<&str>;
+    fn query(&self) -> Option<&str>;
+    fn fragment(&self) -> Option<&str>;
+    fn cannot_be_a_base(&self) -> bool;
+}
+
+/// Borrows an owned [`Host<String>`] as a [`Host<&str>`].
+pub(crate) fn host_as_str(host: &Host<String>) -> Host<&str> {
+    match host {
+        Host::Domain(domain) => Host::Domain(domain.as_str()),
+        Host::Ipv4(v) => Host::Ipv4(*v),
+        Host::Ipv6(v) => Host::Ipv6(*v),
+    }
+}
+
+// https://url.spec.whatwg.org/#url-serializing
+//
+// Writes the serialized string and records the byte offset of every `crate::Position` along
+// the way, so `Display` and `Position`-based slicing are guaranteed to agree: both are driven
+// by this one function instead of duplicating the algorithm.
+pub(crate) fn serialize_with_positions(c: &impl Components) -> (String, [usize; 10]) {
+    let mut out = String::new();
+    let mut offsets = [0usize; 10];
+
+    // BeforeScheme
+    offsets[0] = out.len();
+
+    // 1)
+    if let Some(scheme) = c.scheme() {
+        // In reality URLs have to have a schema. But for special use-cases like URL
+        // truncation, an empty schema might be desireable.
+        let _ = write!(out, "{}:", scheme);
+    }
+
+    // AfterScheme
+    offsets[1] = out.len();
+
+    // 2)
+    if c.host().is_some() {
+        // 2.1)
+        // XXX: special case for no scheme. In these cases, a double slash is probably
+        // not wanted. The `url` crate won't parse URLs starting with a double slash
+        // anyway without having a base URL specified, which this crate does not allow
+        // to do (and probably won't ever).
+        if c.scheme().is_some() {
+            out.push_str("//");
+        }
+
+        if let Some(username) = c.username() {
+            // 2.2.1)
+            out.push_str(username);
+            if let Some(password) = c.password() {
+                if !password.is_empty() {
+                    // 2.2.2)
+                    out.push(':');
+                    out.push_str(password);
+                }
+            }
+
+            // 2.2.3)
+            out.push('@');
+        }
+    }
+
+    // BeforeHost
+    offsets[2] = out.len();
+
+    if let Some(host) = c.host() {
+        // 2.3)
+        match host {
+            Host::Domain(v) => out.push_str(v),
+            Host::Ipv4(v) => {
+                let _ = write!(out, "{}", v);
+            }
+            Host::Ipv6(v) => {
+                let _ = write!(out, "[{}]", v);
+            }
+        }
+
+        // 2.4)
+        if let Some(port) = c.port() {
+            let _ = write!(out, ":{}", port);
+        }
+    }
+
+    // AfterHost
+    offsets[3] = out.len();
+
+    // BeforePath
+    offsets[4] = out.len();
+
+    // 3)
+    if c.cannot_be_a_base() {
+        let first_path_segment = c
+            .path()
+            .and_then(|path| path.strip_prefix('/'))
+            .and_then(|path| path.split('/').next());
+        if let Some(segment) = first_path_segment {
+            out.push_str(segment);
+        }
+    } else {
+        // 4)
+        if let Some(path) = c.path() {
+            // Special case '/' only.
+            if path == "/" {
+                out.push('/');
+            } else {
+                // `path` already carries its own leading '/'; strip it before splitting,
+                // otherwise the split yields a synthetic empty leading segment and the loop
+                // below would write an extra '/' in front of it, doubling up the slash.
+                let rest = path.strip_prefix('/').unwrap_or(path);
+                let path_segments = rest.split('/').collect::<Vec<_>>();
+                if c.host().is_none() && path_segments.len() > 1 && path_segments[0].is_empty() {
+                    out.push_str("/.");
+                }
+
+                for segment in path_segments {
+                    out.push('/');
+                    out.push_str(segment);
+                }
+            }
+        }
+    }
+
+    // AfterPath
+    offsets[5] = out.len();
+
+    // 5)
+    if let Some(query) = c.query() {
+        out.push('?');
+        // BeforeQuery
+        offsets[6] = out.len();
+        out.push_str(query);
+    } else {
+        // BeforeQuery
+        offsets[6] = out.len();
+    }
+
+    // AfterQuery
+    offsets[7] = out.len();
+
+    // 6)
+    if let Some(fragment) = c.fragment() {
+        out.push('#');
+        // BeforeFragment
+        offsets[8] = out.len();
+        out.push_str(fragment);
+    } else {
+        // BeforeFragment
+        offsets[8] = out.len();
+    }
+
+    // AfterFragment
+    offsets[9] = out.len();
+
+    (out, offsets)
+}
+
+pub(crate) fn fmt(c: &impl Components, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let (serialized, _) = serialize_with_positions(c);
+    f.write_str(&serialized)
+}
@@ -1,6 +1,14 @@
 use std::{fmt, str::Split};
 pub use url::*;
 
+mod buf;
+mod components;
+mod position;
+
+pub use buf::UnrestrictiveUrlBuf;
+use components::Components;
+pub use position::{Position, Serialized};
+
 /// A small wrapper around [`url::Url`] that allows free URL modifications.
 ///
 /// Since the [`url`] crate strictly follows the [WHATWG](https://url.spec.whatwg.org/) specification, some operations are deemed illegal and can't be performed with the crate. This crate allows such operations.
@@ -32,103 +40,53 @@ pub struct UnrestrictiveUrl<'a> {
 
 impl<'a> UnrestrictiveUrl<'a> {
     pub fn path_segments(&self) -> Option<Split<'a, char>> {
-        self.path.and_then(|v| {
-            if v.starts_with('/') {
-                Some(v[1..].split('/'))
-            } else {
-                None
-            }
-        })
+        self.path
+            .and_then(|v| v.strip_prefix('/'))
+            .map(|v| v.split('/'))
     }
 }
 
-impl fmt::Display for UnrestrictiveUrl<'_> {
-    // https://url.spec.whatwg.org/#url-serializing
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // 1)
-        if let Some(scheme) = self.scheme {
-            // In reality URLs have to have a schema. But for special use-cases like URL
-            // truncation, an empty schema might be desireable.
-            write!(f, "{}:", scheme)?;
-        }
+impl Components for UnrestrictiveUrl<'_> {
+    fn scheme(&self) -> Option<&str> {
+        self.scheme
+    }
 
-        // 2)
-        if self.host.is_some() {
-            // 2.1)
-            // XXX: special case for no scheme. In these cases, a double slash is probably
-            // not wanted. The `url` crate won't parse URLs starting with a double slash
-            // anyway without having a base URL specified, which this crate does not allow
-            // to do (and probably won't ever).
-            if self.scheme.is_some() {
-                write!(f, "//")?;
-            }
-
-            if let Some(username) = self.username {
-                // 2.2.1)
-                write!(f, "{}", username)?;
-                if let Some(password) = self.password {
-                    if !password.is_empty() {
-                        // 2.2.2)
-                        write!(f, ":{}", password)?;
-                    }
-                }
-
-                // 2.2.3)
-                write!(f, "@")?;
-            }
-
-            // 2.3)
-            match &self.host {
-                Some(host) => match host {
-                    url::Host::Domain(v) => write!(f, "{}", v)?,
-                    url::Host::Ipv4(v) => write!(f, "{}", v)?,
-                    url::Host::Ipv6(v) => write!(f, "[{}]", v)?,
-                },
-                None => {}
-            }
-
-            // 2.4)
-            if let Some(port) = self.port {
-                write!(f, ":{}", port)?;
-            }
-        }
+    fn username(&self) -> Option<&str> {
+        self.username
+    }
 
-        // 3)
-        if self.cannot_be_a_base {
-            let first_path_segment = self.path_segments().and_then(|mut v| v.next());
-            if let Some(segment) = first_path_segment {
-                write!(f, "{}", segment)?;
-            }
-        } else {
-            // 4)
-            if let Some(path) = self.path {
-                // Special case '/' only.
-                if path == "/" {
-                    write!(f, "/")?;
-                } else {
-                    let path_segments = path.split('/').collect::<Vec<_>>();
-                    if self.host.is_none() && path_segments.len() > 1 && path_segments[0] == "" {
-                        write!(f, "/.")?;
-                    }
-
-                    for segment in path_segments {
-                        write!(f, "/{}", segment)?;
-                    }
-                }
-            }
-        }
+    fn password(&self) -> Option<&str> {
+        self.password
+    }
 
-        // 5)
-        if let Some(query) = self.query {
-            write!(f, "?{}", query)?;
-        }
+    fn host(&self) -> Option<url::Host<&str>> {
+        self.host.clone()
+    }
 
-        // 6)
-        if let Some(fragment) = self.fragment {
-            write!(f, "#{}", fragment)?;
-        }
+    fn port(&self) -> Option<u16> {
+        self.port
+    }
+
+    fn path(&self) -> Option<&str> {
+        self.path
+    }
+
+    fn query(&self) -> Option<&str> {
+        self.query
+    }
+
+    fn fragment(&self) -> Option<&str> {
+        self.fragment
+    }
 
-        Ok(())
+    fn cannot_be_a_base(&self) -> bool {
+        self.cannot_be_a_base
+    }
+}
+
+impl fmt::Display for UnrestrictiveUrl<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        components::fmt(self, f)
     }
 }
 
@@ -154,6 +112,16 @@ impl<'a> From<&'a url::Url> for UnrestrictiveUrl<'a> {
     }
 }
 
+impl TryFrom<&UnrestrictiveUrl<'_>> for url::Url {
+    type Error = url::ParseError;
+
+    /// Re-validates the serialized form of an [`UnrestrictiveUrl`] and parses it back into a
+    /// real [`url::Url`], closing the `Url -> UnrestrictiveUrl -> edit -> Url` loop.
+    fn try_from(url: &UnrestrictiveUrl<'_>) -> Result<Self, Self::Error> {
+        url::Url::parse(&url.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{UnrestrictiveUrl, Url};
@@ -217,4 +185,26 @@ mod tests {
 
         assert_eq!("https://github.com/", url.to_string());
     }
+
+    #[test]
+    fn test_try_from_round_trip() {
+        let url = "https://github.com?q=search";
+        let url = Url::parse(url).unwrap();
+        let mut unrestrictive: UnrestrictiveUrl = (&url).into();
+        unrestrictive.scheme = Some("http");
+
+        let url = Url::try_from(&unrestrictive).unwrap();
+        assert_eq!("http://github.com/?q=search", url.as_str());
+    }
+
+    #[test]
+    fn test_try_from_rejects_invalid_urls() {
+        let url = "https://github.com";
+        let url = Url::parse(url).unwrap();
+        let mut unrestrictive: UnrestrictiveUrl = (&url).into();
+        unrestrictive.scheme = None;
+        unrestrictive.host = None;
+
+        assert!(Url::try_from(&unrestrictive).is_err());
+    }
 }
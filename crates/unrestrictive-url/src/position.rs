@@ -0,0 +1,157 @@
+use std::ops::{Index, Range, RangeFrom, RangeTo};
+
+use crate::components::{self, Components};
+
+/// A boundary within the serialized form of an [`crate::UnrestrictiveUrlBuf`].
+///
+/// Mirrors [`url::Position`], but reduced to the boundaries this crate's simplified
+/// serialization actually distinguishes: it doesn't track username, password, or port as
+/// separate components, so e.g. `AfterHost` covers the host *and* its port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Position {
+    BeforeScheme,
+    AfterScheme,
+    BeforeHost,
+    AfterHost,
+    BeforePath,
+    AfterPath,
+    BeforeQuery,
+    AfterQuery,
+    BeforeFragment,
+    AfterFragment,
+}
+
+impl Position {
+    fn slot(self) -> usize {
+        match self {
+            Position::BeforeScheme => 0,
+            Position::AfterScheme => 1,
+            Position::BeforeHost => 2,
+            Position::AfterHost => 3,
+            Position::BeforePath => 4,
+            Position::AfterPath => 5,
+            Position::BeforeQuery => 6,
+            Position::AfterQuery => 7,
+            Position::BeforeFragment => 8,
+            Position::AfterFragment => 9,
+        }
+    }
+}
+
+/// A snapshot of an [`crate::UnrestrictiveUrlBuf`]'s serialized form, with the byte offset of
+/// every [`Position`] precomputed.
+///
+/// `UnrestrictiveUrlBuf`'s fields are owned and freely mutable, so there is no stable buffer to
+/// slice into directly; [`crate::UnrestrictiveUrlBuf::serialize`] materializes one instead, and
+/// `Position`-based indexing operates on that snapshot.
+#[derive(Debug, Clone)]
+pub struct Serialized {
+    text: String,
+    offsets: [usize; 10],
+}
+
+impl Serialized {
+    pub(crate) fn new(c: &impl Components) -> Self {
+        let (text, offsets) = components::serialize_with_positions(c);
+        Self { text, offsets }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+}
+
+impl Index<Range<Position>> for Serialized {
+    type Output = str;
+
+    fn index(&self, range: Range<Position>) -> &str {
+        &self.text[self.offsets[range.start.slot()]..self.offsets[range.end.slot()]]
+    }
+}
+
+impl Index<RangeFrom<Position>> for Serialized {
+    type Output = str;
+
+    fn index(&self, range: RangeFrom<Position>) -> &str {
+        &self.text[self.offsets[range.start.slot()]..]
+    }
+}
+
+impl Index<RangeTo<Position>> for Serialized {
+    type Output = str;
+
+    fn index(&self, range: RangeTo<Position>) -> &str {
+        &self.text[..self.offsets[range.end.slot()]]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Position;
+    use crate::{UnrestrictiveUrlBuf, Url};
+
+    #[test]
+    fn slices_path_onward() {
+        let url = Url::parse("https://github.com?q=search#readme").unwrap();
+        let url = UnrestrictiveUrlBuf::from(&url);
+        let serialized = url.serialize();
+
+        assert_eq!("/?q=search#readme", &serialized[Position::BeforePath..]);
+    }
+
+    #[test]
+    fn slices_up_to_host() {
+        let url = Url::parse("https://github.com?q=search").unwrap();
+        let url = UnrestrictiveUrlBuf::from(&url);
+        let serialized = url.serialize();
+
+        assert_eq!("https://", &serialized[..Position::BeforeHost]);
+    }
+
+    #[test]
+    fn slices_a_bounded_range() {
+        let url = Url::parse("https://github.com?q=search#readme").unwrap();
+        let url = UnrestrictiveUrlBuf::from(&url);
+        let serialized = url.serialize();
+
+        assert_eq!(
+            "q=search",
+            &serialized[Position::BeforeQuery..Position::AfterQuery]
+        );
+    }
+
+    #[test]
+    fn before_and_after_coincide_when_a_component_is_missing() {
+        let url =
+            UnrestrictiveUrlBuf::default().set_host(url::Host::Domain("github.com".to_string()));
+        let serialized = url.serialize();
+
+        assert_eq!(
+            "",
+            &serialized[Position::BeforeQuery..Position::AfterFragment]
+        );
+    }
+
+    #[test]
+    fn positions_stay_consistent_without_a_scheme() {
+        let url = UnrestrictiveUrlBuf::default()
+            .set_host(url::Host::Domain("github.com".to_string()))
+            .set_query("q=search");
+        let serialized = url.serialize();
+
+        assert_eq!(
+            "github.com",
+            &serialized[Position::BeforeHost..Position::AfterHost]
+        );
+        assert_eq!("?q=search", &serialized[Position::BeforePath..]);
+    }
+
+    #[test]
+    fn as_str_matches_display() {
+        let url = Url::parse("https://github.com?q=search").unwrap();
+        let url = UnrestrictiveUrlBuf::from(&url);
+        let serialized = url.serialize();
+
+        assert_eq!(url.to_string(), serialized.as_str());
+    }
+}